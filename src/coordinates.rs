@@ -1,25 +1,16 @@
 use std::{collections::HashSet, sync::LazyLock};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub(crate) struct Coordinate {
     pub(crate) x: i32,
     pub(crate) y: i32,
     pub(crate) z: i32,
 }
 
-impl Coordinate {
-    pub(crate) fn shift(&self, shift: Coordinate) -> Coordinate {
-        Coordinate {
-            x: self.x + shift.x,
-            y: self.y + shift.y,
-            z: self.z + shift.z,
-        }
-    }
-}
-
-#[derive(Eq, PartialEq, Hash)]
+#[derive(Eq, PartialEq, Hash, Clone, Copy)]
 pub(crate) struct Rotation([[i32; 3]; 3]);
 
+// The 24 proper rotations of a cube.
 pub(crate) static ALL_ROTATIONS: LazyLock<Vec<Rotation>> = LazyLock::new(|| {
     let mut all: HashSet<Rotation> = HashSet::new();
 
@@ -43,6 +34,22 @@ pub(crate) static ALL_ROTATIONS: LazyLock<Vec<Rotation>> = LazyLock::new(|| {
     return all.into_iter().collect();
 });
 
+// The full 48-element octahedral group: the 24 proper rotations plus their
+// 24 mirror images, generated by also multiplying each rotation by the
+// inversion matrix `diag(-1, 1, 1)`.
+pub(crate) static ALL_ROTATIONS_AND_REFLECTIONS: LazyLock<Vec<Rotation>> = LazyLock::new(|| {
+    let inversion = Rotation::from_matrix([
+        [-1, 0, 0],
+        [0, 1, 0],
+        [0, 0, 1],
+    ]);
+
+    ALL_ROTATIONS
+        .iter()
+        .flat_map(|rotation| [*rotation, rotation.multiply(&inversion)])
+        .collect()
+});
+
 impl Rotation {
     fn from_matrix(matrix: [[i32; 3]; 3]) -> Self {
         Self(matrix)
@@ -112,3 +119,44 @@ impl Rotation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_rotations_has_24_elements() {
+        assert_eq!(ALL_ROTATIONS.len(), 24);
+    }
+
+    #[test]
+    fn all_rotations_and_reflections_has_48_elements() {
+        assert_eq!(ALL_ROTATIONS_AND_REFLECTIONS.len(), 48);
+    }
+
+    #[test]
+    fn all_rotations_is_closed_under_composition() {
+        for a in ALL_ROTATIONS.iter() {
+            for b in ALL_ROTATIONS.iter() {
+                assert!(ALL_ROTATIONS.contains(&a.multiply(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn every_rotation_preserves_distance_from_the_origin() {
+        let point = Coordinate { x: 1, y: 2, z: 3 };
+        let length_squared = |c: Coordinate| c.x * c.x + c.y * c.y + c.z * c.z;
+
+        for rotation in ALL_ROTATIONS.iter() {
+            assert_eq!(length_squared(rotation.rotate(point)), length_squared(point));
+        }
+    }
+
+    #[test]
+    fn reflections_include_every_proper_rotation() {
+        for rotation in ALL_ROTATIONS.iter() {
+            assert!(ALL_ROTATIONS_AND_REFLECTIONS.contains(rotation));
+        }
+    }
+}
@@ -0,0 +1,304 @@
+// Knuth's Algorithm X via dancing links: finds every way to select a set of
+// rows that covers each column of a sparse 0/1 matrix exactly once. The
+// matrix is a toroidal doubly-linked list; every column also has a header
+// node tracking how many rows still intersect it.
+
+#[derive(Clone, Copy)]
+struct Node {
+    left: usize,
+    right: usize,
+    up: usize,
+    down: usize,
+    column: usize,
+}
+
+// The column `Dlx` would branch on next, as picked by `choose_column`.
+pub(crate) enum NextColumn {
+    // Every column is already covered; the only solution left is empty.
+    AlreadySolved,
+    // No row covers the column with the fewest candidates.
+    Dead,
+    // The node ids of the rows that cover the chosen column.
+    Rows(Vec<usize>),
+}
+
+// Construct with the number of columns and, for each row, the columns it
+// occupies. `cover_next_column` exposes the top-level branches of the
+// search (one per candidate row of the column the S-heuristic would pick),
+// and `solve_from_row` finishes solving a chosen branch.
+#[derive(Clone)]
+pub(crate) struct Dlx {
+    nodes: Vec<Node>,
+    // Indexed by node id; only meaningful for column header nodes (ids
+    // `1..=num_columns`), where it holds the column's remaining row count.
+    size: Vec<usize>,
+    // Indexed by node id; only meaningful for row nodes, where it holds the
+    // row index (as passed to `new`) that node belongs to.
+    row_of: Vec<usize>,
+}
+
+const ROOT: usize = 0;
+
+impl Dlx {
+    pub(crate) fn new(num_columns: usize, rows: &[Vec<usize>]) -> Self {
+        let mut nodes = vec![Node {
+            left: ROOT,
+            right: ROOT,
+            up: ROOT,
+            down: ROOT,
+            column: ROOT,
+        }];
+        let mut row_of = vec![0];
+
+        // Column header nodes, linked left-right in a circle with the root.
+        for column in 0..num_columns {
+            let id = nodes.len();
+            let left = nodes[ROOT].left;
+            nodes.push(Node {
+                left,
+                right: ROOT,
+                up: id,
+                down: id,
+                column: id,
+            });
+            row_of.push(0);
+            nodes[left].right = id;
+            nodes[ROOT].left = id;
+            debug_assert_eq!(id, column + 1);
+        }
+
+        let mut size = vec![0; nodes.len()];
+
+        for (row_index, columns) in rows.iter().enumerate() {
+            let mut row_node_ids = Vec::with_capacity(columns.len());
+
+            for &column in columns {
+                let header = 1 + column;
+                let id = nodes.len();
+                let up = nodes[header].up;
+                nodes.push(Node {
+                    left: id,
+                    right: id,
+                    up,
+                    down: header,
+                    column: header,
+                });
+                row_of.push(row_index);
+                size.push(0);
+
+                nodes[up].down = id;
+                nodes[header].up = id;
+                size[header] += 1;
+
+                row_node_ids.push(id);
+            }
+
+            // Link this row's nodes into a left-right circle.
+            let n = row_node_ids.len();
+            for (i, &id) in row_node_ids.iter().enumerate() {
+                nodes[id].left = row_node_ids[(i + n - 1) % n];
+                nodes[id].right = row_node_ids[(i + 1) % n];
+            }
+        }
+
+        Self {
+            nodes,
+            size,
+            row_of,
+        }
+    }
+
+    // Unlinks `column` from the header row, then removes every row that
+    // intersects it.
+    fn cover(&mut self, column: usize) {
+        let right = self.nodes[column].right;
+        let left = self.nodes[column].left;
+        self.nodes[right].left = left;
+        self.nodes[left].right = right;
+
+        let mut row = self.nodes[column].down;
+        while row != column {
+            let mut node = self.nodes[row].right;
+            while node != row {
+                let down = self.nodes[node].down;
+                let up = self.nodes[node].up;
+                self.nodes[down].up = up;
+                self.nodes[up].down = down;
+                self.size[self.nodes[node].column] -= 1;
+                node = self.nodes[node].right;
+            }
+            row = self.nodes[row].down;
+        }
+    }
+
+    // The exact reverse of `cover`.
+    fn uncover(&mut self, column: usize) {
+        let mut row = self.nodes[column].up;
+        while row != column {
+            let mut node = self.nodes[row].left;
+            while node != row {
+                self.size[self.nodes[node].column] += 1;
+                let down = self.nodes[node].down;
+                let up = self.nodes[node].up;
+                self.nodes[down].up = node;
+                self.nodes[up].down = node;
+                node = self.nodes[node].left;
+            }
+            row = self.nodes[row].up;
+        }
+
+        let right = self.nodes[column].right;
+        let left = self.nodes[column].left;
+        self.nodes[right].left = column;
+        self.nodes[left].right = column;
+    }
+
+    // The S-heuristic: picks the column with the fewest remaining candidate
+    // rows, which prunes far more aggressively than a fixed column order.
+    // Returns `None` once every column is already covered.
+    fn choose_column(&self) -> Option<usize> {
+        let mut column = self.nodes[ROOT].right;
+        if column == ROOT {
+            return None;
+        }
+
+        let mut best = column;
+        while column != ROOT {
+            if self.size[column] < self.size[best] {
+                best = column;
+            }
+            column = self.nodes[column].right;
+        }
+        Some(best)
+    }
+
+    // Covers the column `choose_column` would pick and returns the
+    // candidate rows for it, so each can be explored independently (e.g. on
+    // separate threads) via `solve_from_row`.
+    pub(crate) fn cover_next_column(&mut self) -> NextColumn {
+        let Some(column) = self.choose_column() else {
+            return NextColumn::AlreadySolved;
+        };
+
+        if self.size[column] == 0 {
+            return NextColumn::Dead;
+        }
+
+        self.cover(column);
+
+        let mut rows = Vec::new();
+        let mut row = self.nodes[column].down;
+        while row != column {
+            rows.push(row);
+            row = self.nodes[row].down;
+        }
+        NextColumn::Rows(rows)
+    }
+
+    // Finishes solving assuming `row` (one of the rows returned alongside
+    // the column it was covered under) is selected. `self` must not be
+    // reused afterwards for any other row in that same set.
+    pub(crate) fn solve_from_row(&mut self, row: usize) -> Vec<Vec<usize>> {
+        let mut partial = vec![row];
+
+        let mut node = self.nodes[row].right;
+        while node != row {
+            self.cover(self.nodes[node].column);
+            node = self.nodes[node].right;
+        }
+
+        let mut solutions = Vec::new();
+        self.search(&mut partial, &mut solutions);
+        solutions
+    }
+
+    fn search(&mut self, partial: &mut Vec<usize>, solutions: &mut Vec<Vec<usize>>) {
+        if self.nodes[ROOT].right == ROOT {
+            solutions.push(partial.iter().map(|&id| self.row_of[id]).collect());
+            return;
+        }
+
+        let column = self
+            .choose_column()
+            .expect("header list is non-empty (checked above)");
+
+        if self.size[column] == 0 {
+            // No row can cover this column, so this branch can't lead to a
+            // solution.
+            return;
+        }
+
+        self.cover(column);
+
+        let mut row = self.nodes[column].down;
+        while row != column {
+            partial.push(row);
+
+            let mut node = self.nodes[row].right;
+            while node != row {
+                self.cover(self.nodes[node].column);
+                node = self.nodes[node].right;
+            }
+
+            self.search(partial, solutions);
+
+            let mut node = self.nodes[row].left;
+            while node != row {
+                self.uncover(self.nodes[node].column);
+                node = self.nodes[node].left;
+            }
+
+            partial.pop();
+            row = self.nodes[row].down;
+        }
+
+        self.uncover(column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(num_columns: usize, rows: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut dlx = Dlx::new(num_columns, rows);
+        match dlx.cover_next_column() {
+            NextColumn::AlreadySolved => vec![vec![]],
+            NextColumn::Dead => vec![],
+            NextColumn::Rows(candidate_rows) => candidate_rows
+                .into_iter()
+                .flat_map(|row| dlx.clone().solve_from_row(row))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn finds_the_unique_exact_cover() {
+        // Knuth's example matrix from the "Dancing Links" paper.
+        let rows = vec![
+            vec![0, 3, 6],
+            vec![0, 3],
+            vec![3, 4, 6],
+            vec![2, 4, 5],
+            vec![1, 2, 5, 6],
+            vec![1, 6],
+        ];
+
+        let mut solutions = solve(7, &rows);
+        assert_eq!(solutions.len(), 1);
+        solutions[0].sort();
+        assert_eq!(solutions[0], vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn reports_an_uncoverable_matrix_as_unsolvable() {
+        // Column 1 has no candidate row, so no exact cover is possible.
+        let rows = vec![vec![0]];
+        assert_eq!(solve(2, &rows), Vec::<Vec<usize>>::new());
+    }
+
+    #[test]
+    fn an_empty_matrix_has_exactly_the_empty_solution() {
+        assert_eq!(solve(0, &[]), vec![Vec::<usize>::new()]);
+    }
+}
@@ -1,31 +1,111 @@
 mod coordinates;
+mod dlx;
+mod spec;
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::Read;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
 use coordinates::Coordinate;
 use coordinates::Rotation;
 use coordinates::ALL_ROTATIONS;
+use coordinates::ALL_ROTATIONS_AND_REFLECTIONS;
+use dlx::Dlx;
+use dlx::NextColumn;
+use spec::PuzzleSpec;
+
+// A bitset of occupied cells within a growable bounding box, rather than a
+// fixed cube. `offset` is the box's local origin and `size` its extent
+// along each axis. `bits` is indexed relative to that box, so two `Hitmap`s
+// can only be compared cell-by-cell via their (global) `coordinates()`.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
+pub(crate) struct Hitmap {
+    bits: u128,
+    offset: Coordinate,
+    size: Coordinate,
+}
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
-struct Hitmap(i32);
+// A `Hitmap`'s bounding box grew past the 128 cells `bits` can address.
+#[derive(Debug)]
+pub(crate) struct HitmapTooLarge {
+    pub(crate) cell_count: i64,
+}
+
+impl std::fmt::Display for HitmapTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bounding box ({} cells) exceeds the 128-bit capacity of a Hitmap",
+            self.cell_count
+        )
+    }
+}
+
+impl std::error::Error for HitmapTooLarge {}
 
 impl Hitmap {
-    fn from_coordinates(coords: Vec<Coordinate>) -> Self {
-        let mut hitmap = Hitmap(0);
+    pub(crate) fn from_coordinates(coords: Vec<Coordinate>) -> Result<Self, HitmapTooLarge> {
+        let mut hitmap = Hitmap::empty();
         for coord in coords {
-            hitmap = hitmap.add(coord);
+            hitmap = hitmap.add(coord)?;
         }
 
-        return hitmap;
+        Ok(hitmap)
     }
 
-    fn add(self, coord: Coordinate) -> Self {
-        let index = Self::coordinate_to_index(&coord);
-        Hitmap(self.0 | (1 << index))
+    fn add(self, coord: Coordinate) -> Result<Self, HitmapTooLarge> {
+        let (offset, size) = if self.is_empty() {
+            (coord, Coordinate { x: 1, y: 1, z: 1 })
+        } else {
+            let mut offset = self.offset;
+            let mut size = self.size;
+            Self::widen(&mut offset.x, &mut size.x, coord.x);
+            Self::widen(&mut offset.y, &mut size.y, coord.y);
+            Self::widen(&mut offset.z, &mut size.z, coord.z);
+            (offset, size)
+        };
+
+        let cell_count = size.x as i64 * size.y as i64 * size.z as i64;
+        if cell_count > 128 {
+            return Err(HitmapTooLarge { cell_count });
+        }
+
+        let mut hitmap = Hitmap {
+            bits: 0,
+            offset,
+            size,
+        };
+        for existing in self.coordinates() {
+            hitmap.bits |= 1 << hitmap.local_index(&existing);
+        }
+        hitmap.bits |= 1 << hitmap.local_index(&coord);
+
+        Ok(hitmap)
     }
 
     fn empty() -> Self {
-        return Self::from_coordinates(Vec::new());
+        Hitmap {
+            bits: 0,
+            offset: Coordinate { x: 0, y: 0, z: 0 },
+            size: Coordinate { x: 0, y: 0, z: 0 },
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size.x == 0 || self.size.y == 0 || self.size.z == 0
+    }
+
+    // Grows `(axis_offset, axis_size)` along one axis just enough to also
+    // contain `value`, leaving it unchanged if `value` already fits.
+    fn widen(axis_offset: &mut i32, axis_size: &mut i32, value: i32) {
+        if value < *axis_offset {
+            *axis_size += *axis_offset - value;
+            *axis_offset = value;
+        } else if value >= *axis_offset + *axis_size {
+            *axis_size = value - *axis_offset + 1;
+        }
     }
 
     fn rotate(&self, rotation: &Rotation) -> Hitmap {
@@ -35,30 +115,38 @@ impl Hitmap {
             .map(|coord| rotation.rotate(coord))
             .collect();
 
-        return Self::from_coordinates(rotated_coords);
+        Self::from_coordinates(rotated_coords)
+            .expect("rotating a Hitmap permutes its bounding box's dimensions, not their product")
     }
 
-    fn shift(&self, shift: Coordinate) -> Hitmap {
-        let shifted_coords: Vec<Coordinate> = self
-            .coordinates()
-            .into_iter()
-            .map(|coord| coord.shift(shift))
-            .collect();
-
-        return Self::from_coordinates(shifted_coords);
+    // Moves the box by `delta`; the cells' relative positions (and so
+    // `bits`) don't change.
+    fn translate(&self, delta: Coordinate) -> Hitmap {
+        Hitmap {
+            bits: self.bits,
+            offset: Coordinate {
+                x: self.offset.x + delta.x,
+                y: self.offset.y + delta.y,
+                z: self.offset.z + delta.z,
+            },
+            size: self.size,
+        }
     }
 
     fn coordinates(&self) -> Vec<Coordinate> {
         let mut coords = Vec::new();
 
-        for x in -1..=1 {
-            for y in -1..=1 {
-                for z in -1..=1 {
-                    let coordinate = Coordinate { x, y, z };
-                    let index = Self::coordinate_to_index(&coordinate);
-
-                    if self.0 & (1 << index) != 0 {
-                        coords.push(coordinate);
+        for z in 0..self.size.z {
+            for y in 0..self.size.y {
+                for x in 0..self.size.x {
+                    let index = x + self.size.x * (y + self.size.y * z);
+
+                    if self.bits & (1 << index) != 0 {
+                        coords.push(Coordinate {
+                            x: self.offset.x + x,
+                            y: self.offset.y + y,
+                            z: self.offset.z + z,
+                        });
                     }
                 }
             }
@@ -67,52 +155,115 @@ impl Hitmap {
         coords
     }
 
-    fn coordinate_to_index(coord: &Coordinate) -> i32 {
-        assert!(coord.x == -1 || coord.x == 0 || coord.x == 1);
-        assert!(coord.y == -1 || coord.y == 0 || coord.y == 1);
-        assert!(coord.z == -1 || coord.z == 0 || coord.z == 1);
+    // Converts a global `coord` into an index into `bits`, relative to this
+    // `Hitmap`'s own bounding box. `coord` must already lie within that box.
+    fn local_index(&self, coord: &Coordinate) -> u32 {
+        let local = Coordinate {
+            x: coord.x - self.offset.x,
+            y: coord.y - self.offset.y,
+            z: coord.z - self.offset.z,
+        };
+
+        assert!(local.x >= 0 && local.x < self.size.x);
+        assert!(local.y >= 0 && local.y < self.size.y);
+        assert!(local.z >= 0 && local.z < self.size.z);
 
-        coord.x + 1 + 3 * (coord.y + 1) + 9 * (coord.z + 1)
+        (local.x + self.size.x * (local.y + self.size.y * local.z)) as u32
     }
-}
 
-struct HitmapBuilder {
-    hitmap: Hitmap,
-    coordinate: Coordinate,
-}
+    fn contains(&self, coord: &Coordinate) -> bool {
+        if self.is_empty() {
+            return false;
+        }
 
-impl HitmapBuilder {
-    fn new(coordinate: Coordinate) -> Self {
-        Self {
-            hitmap: Hitmap::empty(),
-            coordinate,
+        let local = Coordinate {
+            x: coord.x - self.offset.x,
+            y: coord.y - self.offset.y,
+            z: coord.z - self.offset.z,
+        };
+        if local.x < 0 || local.x >= self.size.x {
+            return false;
+        }
+        if local.y < 0 || local.y >= self.size.y {
+            return false;
         }
-        .teleport(coordinate)
+        if local.z < 0 || local.z >= self.size.z {
+            return false;
+        }
+
+        let index = local.x + self.size.x * (local.y + self.size.y * local.z);
+        self.bits & (1 << index) != 0
     }
 
-    fn teleport(mut self, coordinate: Coordinate) -> Self {
-        self.coordinate = coordinate;
-        self.hitmap = self.hitmap.add(coordinate);
-        self
+    // Two `Hitmap`s can have different bounding boxes, so this (and
+    // `union`) has to compare cell-by-cell rather than as a single bitwise
+    // op the way a fixed-size bitset could.
+    fn intersects(&self, other: &Hitmap) -> bool {
+        other.coordinates().iter().any(|coord| self.contains(coord))
     }
 
-    fn shift(self, amount: Coordinate) -> Self {
-        let new_coordinate = self.coordinate.shift(amount);
-        self.teleport(new_coordinate)
+    fn union(&self, other: &Hitmap) -> Hitmap {
+        let mut hitmap = *self;
+        for coord in other.coordinates() {
+            hitmap = hitmap
+                .add(coord)
+                .expect("a piece's cells already fit within the target cage's bounding box");
+        }
+        hitmap
+    }
+
+    // Every translation of this (already-rotated) shape whose cells all
+    // land inside `cage`. Exhaustive rather than clever: it only tries the
+    // translations whose bounding box stays within `cage`'s, which for
+    // puzzle-sized cages is a small search.
+    fn placements_within_cage(&self, cage: &Hitmap) -> Vec<Hitmap> {
+        let mut placements = Vec::new();
+        if self.is_empty() {
+            return placements;
+        }
+
+        let dx_min = cage.offset.x - self.offset.x;
+        let dx_max = cage.offset.x + cage.size.x - self.size.x - self.offset.x;
+        let dy_min = cage.offset.y - self.offset.y;
+        let dy_max = cage.offset.y + cage.size.y - self.size.y - self.offset.y;
+        let dz_min = cage.offset.z - self.offset.z;
+        let dz_max = cage.offset.z + cage.size.z - self.size.z - self.offset.z;
+
+        for dx in dx_min..=dx_max {
+            for dy in dy_min..=dy_max {
+                for dz in dz_min..=dz_max {
+                    let placement = self.translate(Coordinate { x: dx, y: dy, z: dz });
+                    if placement
+                        .coordinates()
+                        .iter()
+                        .all(|coord| cage.contains(coord))
+                    {
+                        placements.push(placement);
+                    }
+                }
+            }
+        }
+
+        placements
     }
 }
 
 #[derive(Eq, PartialEq, Hash, Clone)]
 struct Cage {
+    // The target shape being packed, fixed for the whole search; `hitmap`
+    // (the pieces' own footprint) is only ever a subset of it.
+    cage: Hitmap,
     hitmap: Hitmap,
     pieces: Vec<Hitmap>,
 }
 
+#[derive(Debug)]
 struct PieceDoesNotFit;
 
 impl Cage {
-    fn new() -> Self {
+    fn new(cage: Hitmap) -> Self {
         Cage {
+            cage,
             hitmap: Hitmap::empty(),
             pieces: Vec::new(),
         }
@@ -121,7 +272,7 @@ impl Cage {
     fn add(&self, piece: Hitmap) -> Result<Cage, PieceDoesNotFit> {
         // If this piece intersects with the stuff already in the
         // cage, then it can't fit!
-        if self.hitmap.0 & piece.0 != 0 {
+        if self.hitmap.intersects(&piece) {
             return Err(PieceDoesNotFit);
         }
 
@@ -130,14 +281,15 @@ impl Cage {
         new_pieces.sort();
 
         Ok(Cage {
-            hitmap: Hitmap(self.hitmap.0 | piece.0),
+            cage: self.cage,
+            hitmap: self.hitmap.union(&piece),
             pieces: new_pieces,
         })
     }
 
-    fn canonicalize(&self) -> Cage {
+    fn canonicalize(&self, symmetries: &[Rotation]) -> Cage {
         let mut canon_cage = self.clone();
-        for rotation in &*ALL_ROTATIONS {
+        for rotation in symmetries {
             let new_hitmap = self.hitmap.rotate(rotation);
             if new_hitmap <= canon_cage.hitmap {
                 let new_pieces = self
@@ -155,94 +307,350 @@ impl Cage {
 
                 canon_cage.hitmap = new_hitmap;
                 canon_cage.pieces = new_pieces;
+                canon_cage.cage = self.cage.rotate(rotation);
             }
         }
         return canon_cage;
     }
+
+    // Renders the target cage as one character grid per z-layer, labeling
+    // each cell with the letter of the piece occupying it (or `.` if
+    // empty or outside the cage).
+    fn render(&self) -> String {
+        const EMPTY_CELL: char = '.';
+        const PIECE_LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        let mut output = String::new();
+
+        for z in 0..self.cage.size.z {
+            for y in 0..self.cage.size.y {
+                for x in 0..self.cage.size.x {
+                    let coord = Coordinate {
+                        x: self.cage.offset.x + x,
+                        y: self.cage.offset.y + y,
+                        z: self.cage.offset.z + z,
+                    };
+
+                    let label = self
+                        .pieces
+                        .iter()
+                        .position(|piece| piece.contains(&coord))
+                        .map(|index| PIECE_LABELS[index % PIECE_LABELS.len()] as char)
+                        .unwrap_or(EMPTY_CELL);
+
+                    output.push(label);
+                }
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        output
+    }
 }
 
 struct Search {
-    all_pieces: HashSet<Hitmap>,
+    base_pieces: Vec<Hitmap>,
+    cage: Hitmap,
+    include_reflections: bool,
+    threads: usize,
 }
 
 impl Search {
-    fn new() -> Self {
-        let mut all_pieces = HashSet::new();
+    fn from_spec(spec: PuzzleSpec) -> Self {
+        Self {
+            base_pieces: spec.pieces,
+            cage: spec.cage,
+            include_reflections: false,
+            threads: Self::default_threads(),
+        }
+    }
 
-        let x = Coordinate { x: 1, y: 0, z: 0 };
-        let y = Coordinate { x: 0, y: 1, z: 0 };
-        let z = Coordinate { x: 0, y: 0, z: 1 };
-        let corner = Coordinate {
-            x: -1,
-            y: -1,
-            z: -1,
-        };
-        let mut builder = HitmapBuilder::new(corner);
-        builder = builder.shift(x);
-        builder = builder.shift(x);
-        builder = builder.shift(z);
-        builder = builder.teleport(corner);
-        builder = builder.shift(z);
-        builder = builder.teleport(corner);
-        builder = builder.shift(y);
-        builder = builder.shift(y);
+    fn default_threads() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
 
-        let piece1 = builder.hitmap;
+    fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
 
-        // There's only 1 shift we can do to the piece that lets it still fit. Everything else is
-        // rotations.
-        let piece2 = piece1.shift(z);
+    // By default, a cage and its mirror image canonicalize to distinct
+    // solutions. Passing `true` collapses mirror-equivalent cages to a
+    // single representative, as if pieces could be flipped over.
+    fn with_reflections(mut self, include_reflections: bool) -> Self {
+        self.include_reflections = include_reflections;
+        self
+    }
 
-        for rotation in &*ALL_ROTATIONS {
-            all_pieces.insert(piece1.rotate(rotation));
-            all_pieces.insert(piece2.rotate(rotation));
+    fn symmetries(&self) -> &'static [Rotation] {
+        if self.include_reflections {
+            &ALL_ROTATIONS_AND_REFLECTIONS
+        } else {
+            &ALL_ROTATIONS
         }
+    }
 
-        Self { all_pieces }
+    fn row_indices_to_cage(
+        cage: Hitmap,
+        pieces: &[Hitmap],
+        rows: &[Hitmap],
+        row_indices: Vec<usize>,
+    ) -> Cage {
+        let mut cage = Cage::new(cage);
+        for row_index in row_indices {
+            if row_index < pieces.len() {
+                cage = cage
+                    .add(rows[row_index])
+                    .expect("dlx only ever selects non-overlapping rows");
+            }
+        }
+        cage
     }
 
     fn search(self) -> HashSet<Cage> {
-        let mut fringe: Vec<Cage> = vec![Cage::new()];
-
-        let mut canonical_end_states = HashSet::new();
-
-        loop {
-            let cage = match fringe.pop() {
-                None => break,
-                Some(cage) => cage,
-            };
-
-            let mut is_end_state = true;
-            for piece in &self.all_pieces {
-                match cage.add(*piece) {
-                    Err(PieceDoesNotFit) => continue,
-                    Ok(new_cage) => {
-                        is_end_state = false;
-                        fringe.push(new_cage);
-                    }
+        let symmetries = self.symmetries();
+
+        // Every way to rotate and then translate a base piece so it lands
+        // entirely within the target cage. `all_pieces` is a `HashSet`, so
+        // it dedups placements reachable from more than one base piece (or
+        // more than one rotation of the same piece) down to a single row —
+        // Algorithm X picks *some* set of non-overlapping placements that
+        // cover the cage, with no memory of which declared piece a
+        // placement came from. A solution using one piece's shape twice in
+        // place of a different, non-congruent declared piece would go
+        // undetected; `main`'s `solution.pieces.len() == piece_count` check
+        // only catches the placement *count* coming out wrong, not which
+        // shapes made it up.
+        let mut all_pieces = HashSet::new();
+        for base_piece in &self.base_pieces {
+            for rotation in symmetries {
+                let rotated = base_piece.rotate(rotation);
+                for placement in rotated.placements_within_cage(&self.cage) {
+                    all_pieces.insert(placement);
                 }
             }
+        }
+        let pieces: Vec<Hitmap> = all_pieces.into_iter().collect();
+
+        // A `Hitmap`'s own bounding box is local to itself, so number every
+        // cell of the target cage into one shared global column space.
+        let mut columns: HashMap<Coordinate, usize> = HashMap::new();
+        for coord in self.cage.coordinates() {
+            let next_index = columns.len();
+            columns.entry(coord).or_insert(next_index);
+        }
 
-            if is_end_state {
-                let cage = cage.canonicalize();
-                canonical_end_states.insert(cage.clone());
-            }
+        // Algorithm X only finds *exact* covers, but a packed cage is
+        // allowed to leave cells empty. Pad the matrix with one
+        // single-cell "filler" row per cell, so any leftover cells can
+        // always be exactly covered by themselves; these are dropped again
+        // below when a solution is turned back into a `Cage`.
+        let mut rows = pieces.clone();
+        for &coord in columns.keys() {
+            rows.push(
+                Hitmap::from_coordinates(vec![coord])
+                    .expect("a single cell always fits within the 128-bit capacity"),
+            );
         }
 
+        let row_columns: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|hitmap| {
+                hitmap
+                    .coordinates()
+                    .into_iter()
+                    .map(|coord| columns[&coord])
+                    .collect()
+            })
+            .collect();
+
+        let mut dlx = Dlx::new(columns.len(), &row_columns);
+
+        // Fan the top-level branches of the search out across a pool of
+        // worker threads. Each branch's subtree can take wildly different
+        // amounts of work, so threads pull the next branch off a shared
+        // queue (`next_branch`) instead of each being assigned a fixed
+        // share up front.
+        let branch_rows = match dlx.cover_next_column() {
+            NextColumn::AlreadySolved => {
+                // Only possible if the cage itself is empty.
+                return HashSet::from([Cage::new(self.cage).canonicalize(symmetries)])
+            }
+            NextColumn::Dead => return HashSet::new(),
+            NextColumn::Rows(rows) => rows,
+        };
+        let dlx = dlx;
+
+        let next_branch = AtomicUsize::new(0);
+        let num_threads = self.threads.min(branch_rows.len()).max(1);
+
+        let thread_end_states: Vec<HashSet<Cage>> = std::thread::scope(|scope| {
+            (0..num_threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut end_states = HashSet::new();
+                        loop {
+                            let index = next_branch.fetch_add(1, Ordering::Relaxed);
+                            let Some(&row) = branch_rows.get(index) else {
+                                break;
+                            };
+
+                            for row_indices in dlx.clone().solve_from_row(row) {
+                                let cage =
+                                    Self::row_indices_to_cage(self.cage, &pieces, &rows, row_indices);
+                                end_states.insert(cage.canonicalize(symmetries));
+                            }
+                        }
+                        end_states
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("search worker thread panicked"))
+                .collect()
+        });
+
+        let mut canonical_end_states = HashSet::new();
+        for end_states in thread_end_states {
+            canonical_end_states.extend(end_states);
+        }
         canonical_end_states
     }
 }
 
-fn main() {
-    let search = Search::new();
+// Reads the puzzle spec from the path given as the first argument, or from
+// stdin if none was given.
+fn read_spec_input() -> String {
+    let mut input = String::new();
+    match std::env::args().nth(1) {
+        Some(path) => {
+            input = std::fs::read_to_string(&path)
+                .unwrap_or_else(|error| panic!("failed to read puzzle spec {path:?}: {error}"));
+        }
+        None => {
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .expect("failed to read puzzle spec from stdin");
+        }
+    }
+    input
+}
 
-    let solutions = search.search();
-    for solution in solutions {
-        if solution.pieces.len() == 3 {
+fn main() {
+    let input = read_spec_input();
+    let spec = spec::parse(&input).unwrap_or_else(|error| {
+        eprintln!("invalid puzzle spec: {error}");
+        std::process::exit(1);
+    });
+    let piece_count = spec.pieces.len();
+
+    let mut search = match std::env::var("CAGE_THREADS")
+        .ok()
+        .and_then(|threads| threads.parse().ok())
+    {
+        Some(threads) => Search::from_spec(spec).with_threads(threads),
+        None => Search::from_spec(spec),
+    };
+
+    let include_reflections = std::env::var("CAGE_INCLUDE_REFLECTIONS").as_deref() == Ok("1");
+    search = search.with_reflections(include_reflections);
+
+    // Only report packings with exactly as many placements as the spec has
+    // pieces. Which shapes those placements are is not checked, so a packing
+    // that uses one piece's shape in place of another's is not filtered out.
+    for solution in search.search() {
+        if solution.pieces.len() == piece_count {
             println!("Found a solution!");
-            for piece in solution.pieces {
-                println!("{:?}", piece.coordinates());
-            }
+            print!("{}", solution.render());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_coordinates_dedups_repeated_coordinates() {
+        let hitmap = Hitmap::from_coordinates(vec![
+            Coordinate { x: 0, y: 0, z: 0 },
+            Coordinate { x: 1, y: 0, z: 0 },
+            Coordinate { x: 0, y: 0, z: 0 },
+        ])
+        .unwrap();
+
+        let mut coords = hitmap.coordinates();
+        coords.sort();
+        assert_eq!(
+            coords,
+            vec![
+                Coordinate { x: 0, y: 0, z: 0 },
+                Coordinate { x: 1, y: 0, z: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn add_grows_the_bounding_box_to_fit_new_coordinates() {
+        let hitmap = Hitmap::from_coordinates(vec![
+            Coordinate { x: 5, y: 5, z: 5 },
+            Coordinate { x: -1, y: 7, z: 5 },
+        ])
+        .unwrap();
+
+        assert_eq!(hitmap.offset, Coordinate { x: -1, y: 5, z: 5 });
+        assert_eq!(hitmap.size, Coordinate { x: 7, y: 3, z: 1 });
+    }
+
+    #[test]
+    fn a_bounding_box_over_128_cells_is_an_error_not_a_panic() {
+        let coords = (0..130)
+            .map(|x| Coordinate { x, y: 0, z: 0 })
+            .collect();
+
+        let error = Hitmap::from_coordinates(coords).unwrap_err();
+        assert_eq!(error.cell_count, 129);
+    }
+
+    #[test]
+    fn contains_is_false_outside_the_bounding_box() {
+        let hitmap = Hitmap::from_coordinates(vec![Coordinate { x: 0, y: 0, z: 0 }]).unwrap();
+
+        assert!(hitmap.contains(&Coordinate { x: 0, y: 0, z: 0 }));
+        assert!(!hitmap.contains(&Coordinate { x: 1, y: 0, z: 0 }));
+    }
+
+    #[test]
+    fn union_combines_the_cells_of_both_hitmaps() {
+        let a = Hitmap::from_coordinates(vec![Coordinate { x: 0, y: 0, z: 0 }]).unwrap();
+        let b = Hitmap::from_coordinates(vec![Coordinate { x: 1, y: 0, z: 0 }]).unwrap();
+
+        let mut coords = a.union(&b).coordinates();
+        coords.sort();
+        assert_eq!(
+            coords,
+            vec![
+                Coordinate { x: 0, y: 0, z: 0 },
+                Coordinate { x: 1, y: 0, z: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn render_shows_empty_cells_within_the_full_cage_bounds() {
+        let cage_shape = Hitmap::from_coordinates(vec![
+            Coordinate { x: 0, y: 0, z: 0 },
+            Coordinate { x: 1, y: 0, z: 0 },
+            Coordinate { x: 2, y: 0, z: 0 },
+        ])
+        .unwrap();
+        let piece = Hitmap::from_coordinates(vec![Coordinate { x: 2, y: 0, z: 0 }]).unwrap();
+
+        let cage = Cage::new(cage_shape).add(piece).unwrap();
+
+        assert_eq!(cage.render(), "..A\n\n");
+    }
+}
@@ -0,0 +1,180 @@
+// Parses a puzzle spec into a `PuzzleSpec`: one block of stacked ASCII
+// layers per piece, plus one block for the target cage shape. A block
+// starts with a line containing just `piece` or `cage`, followed by its
+// layers; within a block, a blank line starts a new z-layer, and each
+// non-blank line is a row of `#` (occupied) and `.` (empty) cells.
+
+use crate::coordinates::Coordinate;
+use crate::Hitmap;
+
+#[derive(Debug)]
+pub(crate) struct PuzzleSpec {
+    pub(crate) pieces: Vec<Hitmap>,
+    pub(crate) cage: Hitmap,
+}
+
+#[derive(Debug)]
+pub(crate) struct SpecParseError(String);
+
+impl std::fmt::Display for SpecParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SpecParseError {}
+
+enum BlockKind {
+    Piece,
+    Cage,
+}
+
+pub(crate) fn parse(input: &str) -> Result<PuzzleSpec, SpecParseError> {
+    let mut pieces = Vec::new();
+    let mut cage = None;
+
+    let mut block_kind: Option<BlockKind> = None;
+    let mut coords = Vec::new();
+    let mut z = 0;
+    let mut y = 0;
+    let mut layer_has_rows = false;
+
+    for line in input.lines() {
+        let line = line.trim_end();
+
+        if line.eq_ignore_ascii_case("piece") || line.eq_ignore_ascii_case("cage") {
+            finish_block(&mut pieces, &mut cage, block_kind.take(), std::mem::take(&mut coords))?;
+            block_kind = Some(if line.eq_ignore_ascii_case("piece") {
+                BlockKind::Piece
+            } else {
+                BlockKind::Cage
+            });
+            z = 0;
+            y = 0;
+            layer_has_rows = false;
+            continue;
+        }
+
+        if line.is_empty() {
+            if layer_has_rows {
+                z += 1;
+                y = 0;
+                layer_has_rows = false;
+            }
+            continue;
+        }
+
+        if block_kind.is_none() {
+            return Err(SpecParseError(format!(
+                "row outside of a `piece`/`cage` block: {line:?}"
+            )));
+        }
+
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '#' => coords.push(Coordinate {
+                    x: x as i32,
+                    y,
+                    z,
+                }),
+                '.' => {}
+                other => {
+                    return Err(SpecParseError(format!(
+                        "unexpected character {other:?} in layer"
+                    )))
+                }
+            }
+        }
+        y += 1;
+        layer_has_rows = true;
+    }
+
+    finish_block(&mut pieces, &mut cage, block_kind.take(), coords)?;
+
+    let cage = cage.ok_or_else(|| SpecParseError("spec is missing a `cage` block".to_string()))?;
+    if pieces.is_empty() {
+        return Err(SpecParseError("spec has no `piece` blocks".to_string()));
+    }
+
+    Ok(PuzzleSpec { pieces, cage })
+}
+
+fn finish_block(
+    pieces: &mut Vec<Hitmap>,
+    cage: &mut Option<Hitmap>,
+    block_kind: Option<BlockKind>,
+    coords: Vec<Coordinate>,
+) -> Result<(), SpecParseError> {
+    match block_kind {
+        None => Ok(()),
+        Some(BlockKind::Piece) => {
+            pieces.push(Hitmap::from_coordinates(coords).map_err(|error| {
+                SpecParseError(format!("piece block is too large: {error}"))
+            })?);
+            Ok(())
+        }
+        Some(BlockKind::Cage) => {
+            if cage.is_some() {
+                return Err(SpecParseError(
+                    "spec has more than one `cage` block".to_string(),
+                ));
+            }
+            *cage = Some(Hitmap::from_coordinates(coords).map_err(|error| {
+                SpecParseError(format!("cage block is too large: {error}"))
+            })?);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_piece_and_cage_blocks_across_multiple_layers() {
+        let spec = parse("piece\n#.\n..\n\n.#\n..\n\ncage\n##\n##\n").unwrap();
+        assert_eq!(spec.pieces.len(), 1);
+    }
+
+    #[test]
+    fn a_blank_line_starts_a_new_layer_only_after_rows() {
+        // Leading/trailing blank lines shouldn't create empty layers.
+        let spec = parse("\npiece\n#\n\ncage\n#\n\n").unwrap();
+        assert_eq!(spec.pieces.len(), 1);
+
+        // Several blank lines in a row still start only one new layer.
+        let spec = parse("piece\n#\n\n\n#\n\ncage\n#\n").unwrap();
+        assert_eq!(spec.pieces[0].size, Coordinate { x: 1, y: 1, z: 2 });
+    }
+
+    #[test]
+    fn rejects_a_spec_missing_a_cage_block() {
+        let error = parse("piece\n#\n").unwrap_err();
+        assert_eq!(error.0, "spec is missing a `cage` block");
+    }
+
+    #[test]
+    fn rejects_a_spec_with_no_piece_blocks() {
+        let error = parse("cage\n#\n").unwrap_err();
+        assert_eq!(error.0, "spec has no `piece` blocks");
+    }
+
+    #[test]
+    fn rejects_a_spec_with_more_than_one_cage_block() {
+        let error = parse("piece\n#\n\ncage\n#\n\ncage\n#\n").unwrap_err();
+        assert_eq!(error.0, "spec has more than one `cage` block");
+    }
+
+    #[test]
+    fn rejects_a_row_outside_any_block() {
+        let error = parse("#\n").unwrap_err();
+        assert_eq!(error.0, "row outside of a `piece`/`cage` block: \"#\"");
+    }
+
+    #[test]
+    fn rejects_an_unexpected_character() {
+        let error = parse("piece\nXY\n\ncage\n#\n").unwrap_err();
+        assert_eq!(error.0, "unexpected character 'X' in layer");
+    }
+}